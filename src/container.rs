@@ -0,0 +1,164 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{failure, Factory, ProvideNamed};
+
+/// A runtime, type-erased container for dependencies whose set isn't
+/// known until the program is running - plugin loading, config-driven
+/// wiring, and the like. Where `define_context!` builds a fixed struct
+/// of dependencies at compile time, `DiContainer` stores them in a
+/// `HashMap` keyed by `TypeId` and resolves them by downcasting.
+///
+/// `DiContainer` implements `Provide<T>` for any `T: Clone + 'static`,
+/// so functions written against a macro-generated interface
+/// (`fn do_work<I: WorkerInterface>(iface: I)`) can run against a
+/// container built entirely at runtime, with no macro involvement. It
+/// also implements `ProvideNamed<T>`, so two distinct values of the
+/// same type can be registered and resolved under different names.
+#[derive(Default)]
+pub struct DiContainer {
+    values: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    named_values: HashMap<(TypeId, &'static str), Arc<dyn Any + Send + Sync>>,
+}
+
+impl DiContainer {
+    /// Create an empty container.
+    pub fn new() -> Self {
+        DiContainer {
+            values: HashMap::new(),
+            named_values: HashMap::new(),
+        }
+    }
+
+    /// Register an already-built value of type `T`.
+    pub fn register<T: 'static + Send + Sync>(&mut self, value: T) {
+        self.values.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Register an already-built value of type `T` under a name, so it
+    /// can sit alongside other values of the same type.
+    pub fn register_named<T: 'static + Send + Sync>(&mut self, name: &'static str, value: T) {
+        self.named_values.insert((TypeId::of::<T>(), name), Arc::new(value));
+    }
+
+    /// Build a value of type `T` with the given `Factory` and register
+    /// it.
+    pub fn register_factory<T: 'static + Send + Sync, F: Factory<Object = T>>(
+        &mut self,
+    ) -> Result<(), failure::Error> {
+        let dependency = std::any::type_name::<T>();
+        let factory = std::any::type_name::<F>();
+        crate::trace::log_build(dependency, factory);
+        let value = F::build().map_err(|err| crate::trace::wrap_build_error(err, dependency, factory))?;
+        self.register(value);
+        Ok(())
+    }
+
+    /// Resolve a previously registered value of type `T`, cloning it
+    /// out of the container.
+    pub fn resolve<T: 'static + Clone>(&self) -> Result<T, failure::Error> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+            .ok_or_else(|| {
+                failure::format_err!(
+                    "no value of type {} is registered in this DiContainer",
+                    std::any::type_name::<T>(),
+                )
+            })
+    }
+
+    /// Resolve a previously named-registered value of type `T`, cloning
+    /// it out of the container.
+    pub fn resolve_named<T: 'static + Clone + Send + Sync>(
+        &self,
+        name: &'static str,
+    ) -> Result<T, failure::Error> {
+        self.named_values
+            .get(&(TypeId::of::<T>(), name))
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+            .ok_or_else(|| {
+                failure::format_err!(
+                    "no value of type {} is registered under the name {:?} in this DiContainer",
+                    std::any::type_name::<T>(),
+                    name,
+                )
+            })
+    }
+}
+
+impl<T: 'static + Clone> crate::v0_2::Provide<T> for DiContainer {
+    fn provide(&self) -> T {
+        self.resolve::<T>()
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+impl<T: 'static + Clone + Send + Sync> ProvideNamed<T> for DiContainer {
+    fn provide_named(&self, name: &'static str) -> T {
+        self.resolve_named::<T>(name)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct GreeterFactory;
+    impl Factory for GreeterFactory {
+        type Object = String;
+        fn build() -> Result<String, failure::Error> {
+            Ok(String::from("hello"))
+        }
+    }
+
+    #[test]
+    fn register_then_resolve_round_trips() {
+        let mut container = DiContainer::new();
+        container.register(42u32);
+        assert_eq!(container.resolve::<u32>().unwrap(), 42);
+    }
+
+    #[test]
+    fn resolve_missing_type_is_an_error() {
+        let container = DiContainer::new();
+        assert!(container.resolve::<u32>().is_err());
+    }
+
+    #[test]
+    fn resolve_does_not_confuse_same_sized_distinct_types() {
+        let mut container = DiContainer::new();
+        container.register(42u32);
+        assert!(container.resolve::<i32>().is_err());
+    }
+
+    #[test]
+    fn register_factory_builds_and_registers() {
+        let mut container = DiContainer::new();
+        container.register_factory::<String, GreeterFactory>().unwrap();
+        assert_eq!(container.resolve::<String>().unwrap(), "hello");
+    }
+
+    #[test]
+    fn named_values_round_trip_independently_of_unnamed_ones() {
+        let mut container = DiContainer::new();
+        container.register(String::from("default"));
+        container.register_named("audit", String::from("audit log"));
+        container.register_named("app", String::from("app log"));
+
+        assert_eq!(container.resolve::<String>().unwrap(), "default");
+        assert_eq!(container.resolve_named::<String>("audit").unwrap(), "audit log");
+        assert_eq!(container.resolve_named::<String>("app").unwrap(), "app log");
+    }
+
+    #[test]
+    fn resolve_named_missing_name_is_an_error() {
+        let mut container = DiContainer::new();
+        container.register_named("audit", String::from("audit log"));
+        assert!(container.resolve_named::<String>("app").is_err());
+    }
+}
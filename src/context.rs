@@ -0,0 +1,34 @@
+//! Entry point for `define_context!`. The real work of walking the
+//! slot list happens in the `__aerosol_context_munch!` muncher from
+//! `parse.rs`; this module just seeds it with empty accumulators.
+
+/// Declares a context struct with one dependency per slot. Singleton
+/// slots are built eagerly and stored; `lazy` slots are built on first
+/// access and `transient` slots on every access.
+///
+/// ```ignore
+/// aerosol::define_context!(
+///     AppContext {
+///         logger: Arc<Logger> [StdoutLoggerFactory],
+///     }
+/// );
+/// ```
+///
+/// Every slot must end with a trailing comma, including the last one.
+/// See the crate-level docs and the individual `Factory` traits for the
+/// slot shapes this accepts (plain, `async`, `lazy`, `transient`,
+/// `from(...)`, and `@ "name"`).
+#[macro_export]
+macro_rules! define_context {
+    ($vis:vis $name:ident { $($slot:tt)* }) => {
+        $crate::__aerosol_context_munch! {
+            $vis $name;
+            fields: {}
+            sync_inits: {}
+            async_inits: {}
+            names: {}
+            provides: {}
+            remaining: { $($slot)* }
+        }
+    };
+}
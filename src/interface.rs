@@ -0,0 +1,47 @@
+//! Entry point for `define_interface!`. The real work of walking the
+//! method list happens in the `__aerosol_interface_munch!` muncher from
+//! `join.rs`; this module just seeds it with empty accumulators.
+
+/// Declares an interface: a trait that places constraints on whatever
+/// context implements it, plus a blanket impl so every context
+/// satisfying those constraints implements the trait for free.
+///
+/// ```ignore
+/// aerosol::define_interface!(
+///     WorkerInterface {
+///         fn logger(&self) -> Arc<Logger>;
+///     }
+/// );
+/// ```
+///
+/// An interface can inherit the dependencies of other interfaces by
+/// naming them before the body, separated by `+`:
+///
+/// ```ignore
+/// aerosol::define_interface!(
+///     AppInterface: WorkerInterface + Clone {}
+/// );
+/// ```
+///
+/// Every method must end with a trailing semicolon.
+#[macro_export]
+macro_rules! define_interface {
+    ($vis:vis $name:ident : $first:ident $(+ $more:ident)* { $($body:tt)* }) => {
+        $crate::__aerosol_interface_munch! {
+            $vis $name;
+            supers: { $first $($more)* }
+            bounds: {}
+            methods: {}
+            remaining: { $($body)* }
+        }
+    };
+    ($vis:vis $name:ident { $($body:tt)* }) => {
+        $crate::__aerosol_interface_munch! {
+            $vis $name;
+            supers: {}
+            bounds: {}
+            methods: {}
+            remaining: { $($body)* }
+        }
+    };
+}
@@ -0,0 +1,80 @@
+//! Token-munching machinery that turns the body of `define_interface!`
+//! into a trait declaration plus a blanket `impl<T: ...> Trait for T`.
+//! Methods are consumed one at a time (each must end in a trailing
+//! semicolon), accumulating the super-trait bounds and the generated
+//! method bodies, until there are no methods left to process.
+
+/// Recursive muncher driving `define_interface!`. Not part of the
+/// public API - `#[macro_export]` only so it is reachable through
+/// `$crate` from the entry-point macro in `interface.rs`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __aerosol_interface_munch {
+    // Nothing left to process - emit the trait and its blanket impl.
+    (
+        $vis:vis $name:ident;
+        supers: { $($super:ident)* }
+        bounds: { $($bounds:tt)* }
+        methods: { $($methods:tt)* }
+        remaining: {}
+    ) => {
+        $vis trait $name: $($super +)* $($bounds)* Sized {
+            $($methods)*
+        }
+
+        impl<T: $($super +)* $($bounds)* Sized> $name for T {}
+    };
+
+    // `fn method(&self) -> Ty as "name";` - resolved through
+    // `ProvideNamed<Ty>` rather than `Provide<Ty>`, for dependencies
+    // qualified with a name in `define_context!`. Must come before the
+    // plain-method shape, since its pattern is a prefix of it.
+    (
+        $vis:vis $name:ident;
+        supers: { $($super:ident)* }
+        bounds: { $($bounds:tt)* }
+        methods: { $($methods:tt)* }
+        remaining: {
+            fn $method:ident(&self) -> $ty:ty as $qname:literal ;
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::__aerosol_interface_munch! {
+            $vis $name;
+            supers: { $($super)* }
+            bounds: { $($bounds)* $crate::ProvideNamed<$ty> + }
+            methods: { $($methods)*
+                fn $method(&self) -> $ty {
+                    $crate::ProvideNamed::provide_named(self, $qname)
+                }
+            }
+            remaining: { $($rest)* }
+        }
+    };
+
+    // `fn method(&self) -> Ty;` - the plain, unqualified dependency.
+    // Must come after the named-method shape, since its pattern is a
+    // prefix of it.
+    (
+        $vis:vis $name:ident;
+        supers: { $($super:ident)* }
+        bounds: { $($bounds:tt)* }
+        methods: { $($methods:tt)* }
+        remaining: {
+            fn $method:ident(&self) -> $ty:ty ;
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::__aerosol_interface_munch! {
+            $vis $name;
+            supers: { $($super)* }
+            bounds: { $($bounds)* $crate::Provide<$ty> + }
+            methods: { $($methods)*
+                fn $method(&self) -> $ty {
+                    $crate::Provide::provide(self)
+                }
+            }
+            remaining: { $($rest)* }
+        }
+    };
+}
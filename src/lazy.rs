@@ -0,0 +1,36 @@
+use std::sync::Mutex;
+
+use crate::failure;
+
+/// Backing storage for a `lazy` slot in a `define_context!` block.
+///
+/// A singleton slot builds its value once in `new()`; a `lazy` slot
+/// defers that to the first call to `Provide::provide`, caching the
+/// result behind a mutex so later calls are handed the same clone.
+/// Transient slots don't use `Lazy` at all - they store the `Factory`
+/// itself and call `build()` fresh on every access.
+pub struct Lazy<T> {
+    cell: Mutex<Option<T>>,
+}
+
+impl<T: Clone> Lazy<T> {
+    /// Start out empty; the value is built on first access.
+    pub fn new() -> Self {
+        Lazy { cell: Mutex::new(None) }
+    }
+
+    /// Return the cached value, building it with `init` the first time
+    /// this is called.
+    pub fn get_or_try_init<F>(&self, init: F) -> Result<T, failure::Error>
+    where
+        F: FnOnce() -> Result<T, failure::Error>,
+    {
+        let mut guard = self.cell.lock().unwrap();
+        if let Some(value) = &*guard {
+            return Ok(value.clone());
+        }
+        let value = init()?;
+        *guard = Some(value.clone());
+        Ok(value)
+    }
+}
@@ -100,30 +100,66 @@ pub extern crate tt_call;
 pub extern crate failure;
 extern crate v0_2;
 
+use std::future::Future;
+use std::pin::Pin;
+
 mod join;
 mod parse;
 mod interface;
 mod context;
+mod lazy;
+mod container;
+#[doc(hidden)]
+pub mod trace;
 
+pub use lazy::Lazy;
+pub use container::DiContainer;
 
 /// The building block for this crate. Automatically implemented
 /// for contexts providing a dependency of type `T`.
-/// 
+///
 /// Super-trait of all interfaces requiring a dependency of type
 /// `T`.
 pub use v0_2::Provide;
 
+/// Like [`Provide`], but for a dependency qualified with a name rather
+/// than resolved purely by type.
+///
+/// A slot written `field @ "name": Ty [Factory]` in `define_context!`
+/// implements this instead of `Provide<Ty>`; a method written `fn
+/// method(&self) -> Ty as "name";` in `define_interface!` requires it
+/// instead of `Provide<Ty>`. Each named slot generates its own
+/// `impl ProvideNamed<Ty>`, so a macro-generated context can hold at
+/// most one named slot per type: a second named slot of the same type
+/// is a conflicting-impl compile error. `DiContainer` has no such
+/// limit and can hold several named values of one type.
+pub trait ProvideNamed<T> {
+    fn provide_named(&self, name: &'static str) -> T;
+}
+
 /// Implement this trait to provide a convenient syntax for
 /// constructing implementations of dependencies.
+///
+/// A slot in `define_context!` may be annotated with a lifetime mode
+/// that controls when this runs: a slot with no mode is a singleton,
+/// which calls `build()` once in `new()` and hands out clones (there is
+/// no `singleton` keyword; the mode is written by leaving it out); `lazy` defers that
+/// first `build()` to the first `Provide::provide` call, caching the
+/// result behind a [`Lazy`]; `transient` calls `build()` again on every
+/// `provide()`, so each caller gets a fresh instance.
 pub trait Factory {
     type Object;
     fn build() -> Result<Self::Object, failure::Error>;
 }
 
-/// Allows cloning a context whilst replacing one dependency
-/// with a different implementation. Must be explicitly listed
-/// as a super-trait of an interface to use.
-pub use v0_2::ProvideWith;
+// `v0_2::ProvideWith` used to be re-exported here, but `define_context!`
+// has no way to generate a mode-aware impl of it for `lazy`/`transient`
+// slots (there's no stored `Factory` to re-invoke with a different
+// implementation once a slot is built) without knowing the real shape
+// of that trait, which lives entirely in the external `v0_2` crate this
+// snapshot doesn't vendor. Overriding a dependency while cloning a
+// context isn't supported by macro-generated contexts yet; implement
+// `v0_2::ProvideWith` by hand on your context if you need it.
 
 /// Compatibility layer - allows using the newer `Factory` trait with the old `define_context` macro, or vice versa.
 pub struct FactoryAdaptor<T>(pub(crate) T);
@@ -131,13 +167,94 @@ pub struct FactoryAdaptor<T>(pub(crate) T);
 impl<T: v0_2::Factory> Factory for FactoryAdaptor<T> {
     type Object = T::Object;
     fn build() -> Result<Self::Object, failure::Error> {
-        T::build(())
+        trace::log_build(std::any::type_name::<T::Object>(), std::any::type_name::<T>());
+        T::build(()).map_err(|err| {
+            trace::wrap_build_error(err, std::any::type_name::<T::Object>(), std::any::type_name::<T>())
+        })
     }
 }
 
 impl<T: Factory> v0_2::Factory for FactoryAdaptor<T> {
     type Object = T::Object;
     fn build(_: ()) -> Result<Self::Object, failure::Error> {
+        trace::log_build(std::any::type_name::<T::Object>(), std::any::type_name::<T>());
+        T::build().map_err(|err| {
+            trace::wrap_build_error(err, std::any::type_name::<T::Object>(), std::any::type_name::<T>())
+        })
+    }
+}
+
+/// Implement this trait for dependencies that must be constructed with
+/// asynchronous work, such as opening a database pool or a gRPC channel.
+/// Mirrors `Factory`, but hands back a boxed future instead of resolving
+/// synchronously.
+///
+/// A slot declared `async [SomeAsyncFactory]` in `define_context!` is
+/// built by `AppContext::new_async()`, which awaits every async factory
+/// in declaration order while still building the synchronous slots
+/// eagerly.
+pub trait AsyncFactory {
+    type Object;
+    fn build() -> Pin<Box<dyn Future<Output = Result<Self::Object, failure::Error>> + Send>>;
+}
+
+/// Compatibility layer - lets an `AsyncFactory` satisfy a slot declared
+/// with the synchronous `Factory` trait, or vice versa.
+///
+/// Adapting a `Factory` to `AsyncFactory` just wraps the already-built
+/// value in a future that is immediately ready. Adapting an
+/// `AsyncFactory` to `Factory` cannot be done without blocking on an
+/// executor, so that direction panics whenever it is built, from
+/// `new()` and `new_async()` alike; an async factory is only awaited
+/// when it sits in an `async [...]` slot itself.
+pub struct AsyncFactoryAdaptor<T>(pub(crate) T);
+
+impl<T: Factory> AsyncFactory for AsyncFactoryAdaptor<T>
+where
+    T::Object: Send + 'static,
+{
+    type Object = T::Object;
+    fn build() -> Pin<Box<dyn Future<Output = Result<Self::Object, failure::Error>> + Send>> {
+        Box::pin(std::future::ready(T::build()))
+    }
+}
+
+impl<T: AsyncFactory> Factory for AsyncFactoryAdaptor<T> {
+    type Object = T::Object;
+    fn build() -> Result<Self::Object, failure::Error> {
+        panic!(
+            "{} is an async factory and cannot be built synchronously; declare it in an `async [...]` slot and use new_async() instead",
+            std::any::type_name::<T>(),
+        )
+    }
+}
+
+/// Like `Factory`, but for dependencies whose construction needs other
+/// context-provided dependencies - the constructor injection pattern.
+///
+/// A slot written `service: Foo [FooFactory from(logger: Arc<Logger>,
+/// config: Config)]` in `define_context!` builds an ad hoc context
+/// holding just those dependencies and passes it to `build`, so `C`
+/// here is never the full context - only `Provide<Arc<Logger>> +
+/// Provide<Config>` is guaranteed. Dependencies must be `singleton`
+/// (the default) or `async` slots declared earlier in the same block:
+/// referencing one declared later is a compile error from ordinary
+/// name resolution, which doubles as this generator's cycle detection;
+/// referencing a `lazy` or `transient` slot is also a compile error,
+/// since those modes don't keep a field holding the dependency's own
+/// type around to hand off (`lazy` stores a `Lazy<Dty>` it hasn't
+/// necessarily forced yet, and `transient` stores no field at all).
+pub trait FactoryFrom<C> {
+    type Object;
+    fn build(ctx: &C) -> Result<Self::Object, failure::Error>;
+}
+
+/// Lets any plain `Factory` be used where a `FactoryFrom<C>` is
+/// expected, ignoring the context since it has no dependencies to pull
+/// from it.
+impl<C, T: Factory> FactoryFrom<C> for T {
+    type Object = T::Object;
+    fn build(_ctx: &C) -> Result<Self::Object, failure::Error> {
         T::build()
     }
 }
@@ -0,0 +1,338 @@
+//! Token-munching machinery that turns the body of `define_context!`
+//! into a struct definition plus one `Provide`/`ProvideNamed` impl per
+//! slot. Slots are consumed one at a time (each must end in a trailing
+//! comma), accumulating the generated struct fields, the statements
+//! that build `new()`/`new_async()`, and the impl blocks, until there
+//! are no slots left to process.
+
+/// Recursive muncher driving `define_context!`. Not part of the public
+/// API - `#[macro_export]` only so it is reachable through `$crate`
+/// from the entry-point macro in `context.rs`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __aerosol_context_munch {
+    // Nothing left to process - emit the context struct and its impls.
+    (
+        $vis:vis $ctx:ident;
+        fields: { $($fields:tt)* }
+        sync_inits: { $($sync_inits:tt)* }
+        async_inits: { $($async_inits:tt)* }
+        names: { $($names:ident)* }
+        provides: { $($provides:tt)* }
+        remaining: {}
+    ) => {
+        $vis struct $ctx {
+            $($fields)*
+        }
+
+        impl $ctx {
+            /// Build every singleton slot eagerly (`lazy` slots are
+            /// built on first `provide()`, `transient` slots on every
+            /// call). Slots declared `async` cannot be built this way
+            /// and panic instead - use `new_async()` for a context
+            /// containing any of those.
+            pub fn new() -> Result<Self, $crate::failure::Error> {
+                $($sync_inits)*
+                Ok($ctx { $($names),* })
+            }
+
+            /// Build every slot, awaiting each `async` slot's factory
+            /// in declaration order. Synchronous slots are still built
+            /// eagerly, inline.
+            pub async fn new_async() -> Result<Self, $crate::failure::Error> {
+                $($async_inits)*
+                Ok($ctx { $($names),* })
+            }
+        }
+
+        $($provides)*
+    };
+
+    // `field: Ty [async Factory],` - built only by `new_async()`.
+    (
+        $vis:vis $ctx:ident;
+        fields: { $($fields:tt)* }
+        sync_inits: { $($sync_inits:tt)* }
+        async_inits: { $($async_inits:tt)* }
+        names: { $($names:ident)* }
+        provides: { $($provides:tt)* }
+        remaining: {
+            $field:ident : $ty:ty [ async $factory:ident ] ,
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::__aerosol_context_munch! {
+            $vis $ctx;
+            fields: { $($fields)* $field: $ty, }
+            sync_inits: { $($sync_inits)*
+                let $field: $ty = panic!(
+                    "{} is built by an AsyncFactory and cannot be constructed synchronously; use new_async() instead",
+                    stringify!($field),
+                );
+            }
+            async_inits: { $($async_inits)*
+                let $field: $ty = {
+                    $crate::trace::log_build(stringify!($ty), stringify!($factory));
+                    <$factory as $crate::AsyncFactory>::build().await.map_err(|err| {
+                        $crate::trace::wrap_build_error(err, stringify!($ty), stringify!($factory))
+                    })?
+                };
+            }
+            names: { $($names)* $field }
+            provides: { $($provides)*
+                impl $crate::Provide<$ty> for $ctx {
+                    fn provide(&self) -> $ty {
+                        self.$field.clone()
+                    }
+                }
+            }
+            remaining: { $($rest)* }
+        }
+    };
+
+    // `field: Ty [lazy Factory],` - built on first `provide()` call,
+    // then cached.
+    (
+        $vis:vis $ctx:ident;
+        fields: { $($fields:tt)* }
+        sync_inits: { $($sync_inits:tt)* }
+        async_inits: { $($async_inits:tt)* }
+        names: { $($names:ident)* }
+        provides: { $($provides:tt)* }
+        remaining: {
+            $field:ident : $ty:ty [ lazy $factory:ident ] ,
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::__aerosol_context_munch! {
+            $vis $ctx;
+            fields: { $($fields)* $field: $crate::Lazy<$ty>, }
+            sync_inits: { $($sync_inits)* let $field: $crate::Lazy<$ty> = $crate::Lazy::new(); }
+            async_inits: { $($async_inits)* let $field: $crate::Lazy<$ty> = $crate::Lazy::new(); }
+            names: { $($names)* $field }
+            provides: { $($provides)*
+                impl $crate::Provide<$ty> for $ctx {
+                    fn provide(&self) -> $ty {
+                        self.$field
+                            .get_or_try_init(|| {
+                                $crate::trace::log_build(stringify!($ty), stringify!($factory));
+                                <$factory as $crate::Factory>::build().map_err(|err| {
+                                    $crate::trace::wrap_build_error(err, stringify!($ty), stringify!($factory))
+                                })
+                            })
+                            .unwrap_or_else(|err| panic!("{}", err))
+                    }
+                }
+            }
+            remaining: { $($rest)* }
+        }
+    };
+
+    // `field: Ty [transient Factory],` - `Factory::build()` is called
+    // again on every `provide()`, so the slot needs no stored field at
+    // all.
+    (
+        $vis:vis $ctx:ident;
+        fields: { $($fields:tt)* }
+        sync_inits: { $($sync_inits:tt)* }
+        async_inits: { $($async_inits:tt)* }
+        names: { $($names:ident)* }
+        provides: { $($provides:tt)* }
+        remaining: {
+            $field:ident : $ty:ty [ transient $factory:ident ] ,
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::__aerosol_context_munch! {
+            $vis $ctx;
+            fields: { $($fields)* }
+            sync_inits: { $($sync_inits)* }
+            async_inits: { $($async_inits)* }
+            names: { $($names)* }
+            provides: { $($provides)*
+                impl $crate::Provide<$ty> for $ctx {
+                    fn provide(&self) -> $ty {
+                        $crate::trace::log_build(stringify!($ty), stringify!($factory));
+                        <$factory as $crate::Factory>::build()
+                            .map_err(|err| {
+                                $crate::trace::wrap_build_error(err, stringify!($ty), stringify!($factory))
+                            })
+                            .unwrap_or_else(|err| panic!("{}", err))
+                    }
+                }
+            }
+            remaining: { $($rest)* }
+        }
+    };
+
+    // `field: Ty [Factory from(dep: DepTy, ...)],` - constructor
+    // injection. Each dependency must be an earlier `singleton` or
+    // `async` slot in the same `define_context!` block: referencing a
+    // slot that hasn't been declared yet is a plain "cannot find value"
+    // compile error, which doubles as this generator's cycle detection
+    // (there is no separate topological sort, slots are simply built in
+    // the order they're written); referencing a `lazy` or `transient`
+    // slot is also a compile error, since neither stores a plain `DepTy`
+    // field to borrow here (see the `FactoryFrom` doc comment).
+    (
+        $vis:vis $ctx:ident;
+        fields: { $($fields:tt)* }
+        sync_inits: { $($sync_inits:tt)* }
+        async_inits: { $($async_inits:tt)* }
+        names: { $($names:ident)* }
+        provides: { $($provides:tt)* }
+        remaining: {
+            $field:ident : $ty:ty [ $factory:ident from ( $($dep:ident : $dty:ty),* $(,)? ) ] ,
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::__aerosol_context_munch! {
+            $vis $ctx;
+            fields: { $($fields)* $field: $ty, }
+            sync_inits: { $($sync_inits)*
+                let $field: $ty = {
+                    struct __AerosolFromCtx<'a> { $($dep: &'a $dty,)* }
+                    $(
+                        impl<'a> $crate::Provide<$dty> for __AerosolFromCtx<'a> {
+                            fn provide(&self) -> $dty { self.$dep.clone() }
+                        }
+                    )*
+                    let __ctx = __AerosolFromCtx { $($dep: &$dep,)* };
+                    $crate::trace::log_build(stringify!($ty), stringify!($factory));
+                    <$factory as $crate::FactoryFrom<__AerosolFromCtx<'_>>>::build(&__ctx).map_err(|err| {
+                        $crate::trace::wrap_build_error(err, stringify!($ty), stringify!($factory))
+                    })?
+                };
+            }
+            async_inits: { $($async_inits)*
+                let $field: $ty = {
+                    struct __AerosolFromCtx<'a> { $($dep: &'a $dty,)* }
+                    $(
+                        impl<'a> $crate::Provide<$dty> for __AerosolFromCtx<'a> {
+                            fn provide(&self) -> $dty { self.$dep.clone() }
+                        }
+                    )*
+                    let __ctx = __AerosolFromCtx { $($dep: &$dep,)* };
+                    $crate::trace::log_build(stringify!($ty), stringify!($factory));
+                    <$factory as $crate::FactoryFrom<__AerosolFromCtx<'_>>>::build(&__ctx).map_err(|err| {
+                        $crate::trace::wrap_build_error(err, stringify!($ty), stringify!($factory))
+                    })?
+                };
+            }
+            names: { $($names)* $field }
+            provides: { $($provides)*
+                impl $crate::Provide<$ty> for $ctx {
+                    fn provide(&self) -> $ty {
+                        self.$field.clone()
+                    }
+                }
+            }
+            remaining: { $($rest)* }
+        }
+    };
+
+    // `field @ "name": Ty [Factory],` - a slot qualified with a name.
+    // Built eagerly like a plain slot, but resolved through
+    // `ProvideNamed` instead of `Provide`. Each such slot emits its own
+    // `impl ProvideNamed<Ty>`, so a second named slot of the same type
+    // in one context is a conflicting-impl compile error. Must come
+    // before the plain slot shape,
+    // since its pattern is a prefix of it.
+    (
+        $vis:vis $ctx:ident;
+        fields: { $($fields:tt)* }
+        sync_inits: { $($sync_inits:tt)* }
+        async_inits: { $($async_inits:tt)* }
+        names: { $($names:ident)* }
+        provides: { $($provides:tt)* }
+        remaining: {
+            $field:ident @ $qname:literal : $ty:ty [ $factory:ident ] ,
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::__aerosol_context_munch! {
+            $vis $ctx;
+            fields: { $($fields)* $field: $ty, }
+            sync_inits: { $($sync_inits)*
+                let $field: $ty = {
+                    $crate::trace::log_build(stringify!($ty), stringify!($factory));
+                    <$factory as $crate::Factory>::build().map_err(|err| {
+                        $crate::trace::wrap_build_error(err, stringify!($ty), stringify!($factory))
+                    })?
+                };
+            }
+            async_inits: { $($async_inits)*
+                let $field: $ty = {
+                    $crate::trace::log_build(stringify!($ty), stringify!($factory));
+                    <$factory as $crate::Factory>::build().map_err(|err| {
+                        $crate::trace::wrap_build_error(err, stringify!($ty), stringify!($factory))
+                    })?
+                };
+            }
+            names: { $($names)* $field }
+            provides: { $($provides)*
+                impl $crate::ProvideNamed<$ty> for $ctx {
+                    fn provide_named(&self, name: &'static str) -> $ty {
+                        if name == $qname {
+                            self.$field.clone()
+                        } else {
+                            panic!(
+                                "{} has no dependency of type {} named {:?}",
+                                stringify!($ctx),
+                                stringify!($ty),
+                                name,
+                            )
+                        }
+                    }
+                }
+            }
+            remaining: { $($rest)* }
+        }
+    };
+
+    // `field: Ty [Factory],` - the plain, eager singleton slot. Must
+    // come after every other slot shape, since its pattern is a prefix
+    // of all of them.
+    (
+        $vis:vis $ctx:ident;
+        fields: { $($fields:tt)* }
+        sync_inits: { $($sync_inits:tt)* }
+        async_inits: { $($async_inits:tt)* }
+        names: { $($names:ident)* }
+        provides: { $($provides:tt)* }
+        remaining: {
+            $field:ident : $ty:ty [ $factory:ident ] ,
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::__aerosol_context_munch! {
+            $vis $ctx;
+            fields: { $($fields)* $field: $ty, }
+            sync_inits: { $($sync_inits)*
+                let $field: $ty = {
+                    $crate::trace::log_build(stringify!($ty), stringify!($factory));
+                    <$factory as $crate::Factory>::build().map_err(|err| {
+                        $crate::trace::wrap_build_error(err, stringify!($ty), stringify!($factory))
+                    })?
+                };
+            }
+            async_inits: { $($async_inits)*
+                let $field: $ty = {
+                    $crate::trace::log_build(stringify!($ty), stringify!($factory));
+                    <$factory as $crate::Factory>::build().map_err(|err| {
+                        $crate::trace::wrap_build_error(err, stringify!($ty), stringify!($factory))
+                    })?
+                };
+            }
+            names: { $($names)* $field }
+            provides: { $($provides)*
+                impl $crate::Provide<$ty> for $ctx {
+                    fn provide(&self) -> $ty {
+                        self.$field.clone()
+                    }
+                }
+            }
+            remaining: { $($rest)* }
+        }
+    };
+}
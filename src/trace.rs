@@ -0,0 +1,42 @@
+//! Opt-in resolution tracing, enabled by the `trace` cargo feature.
+//!
+//! With the feature off, every helper here is a no-op that the compiler
+//! discards entirely - exactly like `log`'s own `max_level_*`
+//! compile-time filtering, so release builds that don't ask for tracing
+//! pay nothing for it.
+
+// `pub`, not `pub(crate)`: `define_context!` expands these calls into
+// the crate of whoever invokes the macro, so they must be reachable
+// from outside this crate too. `#[doc(hidden)] pub mod trace` on the
+// declaration keeps them out of the public docs despite that.
+
+#[cfg(feature = "trace")]
+pub fn log_build(dependency: &str, factory: &str) {
+    log::trace!("building dependency {:?} via {}", dependency, factory);
+}
+
+#[cfg(not(feature = "trace"))]
+#[inline(always)]
+pub fn log_build(_dependency: &str, _factory: &str) {}
+
+/// Attach a breadcrumb naming the dependency and factory being built to
+/// an error coming out of that factory.
+#[cfg(feature = "trace")]
+pub fn wrap_build_error(
+    err: crate::failure::Error,
+    dependency: &str,
+    factory: &str,
+) -> crate::failure::Error {
+    err.context(format!("building dependency {:?} via {}", dependency, factory))
+        .into()
+}
+
+#[cfg(not(feature = "trace"))]
+#[inline(always)]
+pub fn wrap_build_error(
+    err: crate::failure::Error,
+    _dependency: &str,
+    _factory: &str,
+) -> crate::failure::Error {
+    err
+}
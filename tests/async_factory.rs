@@ -0,0 +1,81 @@
+//! Exercises `AsyncFactory` and the `async [Factory]` slot shape in
+//! `define_context!`.
+
+use aerosol::{AsyncFactory, Factory, Provide, failure};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+struct Config(u32);
+
+struct ConfigFactory;
+impl Factory for ConfigFactory {
+    type Object = Config;
+    fn build() -> Result<Config, failure::Error> {
+        Ok(Config(1))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Pool(Arc<str>);
+
+struct PoolFactory;
+impl AsyncFactory for PoolFactory {
+    type Object = Pool;
+    fn build() -> Pin<Box<dyn Future<Output = Result<Pool, failure::Error>> + Send>> {
+        Box::pin(std::future::ready(Ok(Pool(Arc::from("connected")))))
+    }
+}
+
+aerosol::define_context!(
+    pub AppContext {
+        config: Config [ConfigFactory],
+        pool: Pool [async PoolFactory],
+    }
+);
+
+fn block_on<F: Future>(fut: F) -> F::Output {
+    futures_executor_lite::block_on(fut)
+}
+
+// A minimal single-threaded executor, since this crate doesn't depend
+// on `futures` or `tokio` - it only needs to poll one already-ready
+// future to completion.
+mod futures_executor_lite {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    fn noop_raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            noop_raw_waker()
+        }
+        fn noop(_: *const ()) {}
+        RawWaker::new(std::ptr::null(), &RawWakerVTable::new(clone, noop, noop, noop))
+    }
+
+    pub fn block_on<F: Future>(mut fut: F) -> F::Output {
+        let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+        let mut cx = Context::from_waker(&waker);
+        let mut fut = unsafe { Pin::new_unchecked(&mut fut) };
+        loop {
+            if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+                return out;
+            }
+        }
+    }
+}
+
+#[test]
+fn new_async_builds_both_sync_and_async_slots() {
+    let ctx = block_on(AppContext::new_async()).unwrap();
+    assert_eq!(Provide::<Config>::provide(&ctx).0, 1);
+    assert_eq!(&*Provide::<Pool>::provide(&ctx).0, "connected");
+}
+
+#[test]
+#[should_panic(expected = "cannot be constructed synchronously")]
+fn new_panics_on_a_context_with_an_async_slot() {
+    AppContext::new().ok();
+}
@@ -0,0 +1,52 @@
+//! Exercises the `Factory from(dep: DepTy, ...)` constructor-injection
+//! slot shape in `define_context!` and the underlying `FactoryFrom`
+//! trait.
+
+use aerosol::{Factory, FactoryFrom, Provide, failure};
+
+#[derive(Clone, Debug)]
+struct Config(u32);
+
+struct ConfigFactory;
+impl Factory for ConfigFactory {
+    type Object = Config;
+    fn build() -> Result<Config, failure::Error> {
+        Ok(Config(1))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Greeting(String);
+
+struct GreetingFactory;
+impl<C: Provide<Config>> FactoryFrom<C> for GreetingFactory {
+    type Object = Greeting;
+    fn build(ctx: &C) -> Result<Greeting, failure::Error> {
+        let config = Provide::<Config>::provide(ctx);
+        Ok(Greeting(format!("config is {}", config.0)))
+    }
+}
+
+aerosol::define_context!(
+    pub AppContext {
+        config: Config [ConfigFactory],
+        greeting: Greeting [GreetingFactory from(config: Config)],
+    }
+);
+
+#[test]
+fn from_slot_resolves_its_dependency_from_the_context() {
+    let ctx = AppContext::new().unwrap();
+    assert_eq!(Provide::<Greeting>::provide(&ctx).0, "config is 1");
+}
+
+#[test]
+fn plain_factory_still_works_as_a_factory_from() {
+    // The blanket `impl<C, T: Factory> FactoryFrom<C> for T` means an
+    // ordinary `Factory` can stand in wherever a `FactoryFrom<C>` is
+    // expected, with the context simply ignored.
+    fn build_it<F: FactoryFrom<()>>() -> F::Object {
+        F::build(&()).unwrap()
+    }
+    assert_eq!(build_it::<ConfigFactory>().0, 1);
+}
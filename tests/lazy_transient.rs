@@ -0,0 +1,56 @@
+//! Exercises the `lazy` and `transient` slot shapes in
+//! `define_context!`.
+
+use aerosol::{Factory, Provide, failure};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+static LAZY_BUILDS: AtomicU32 = AtomicU32::new(0);
+static TRANSIENT_BUILDS: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone, Debug)]
+struct LazyThing(u32);
+
+struct LazyThingFactory;
+impl Factory for LazyThingFactory {
+    type Object = LazyThing;
+    fn build() -> Result<LazyThing, failure::Error> {
+        Ok(LazyThing(LAZY_BUILDS.fetch_add(1, Ordering::SeqCst)))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct TransientThing(u32);
+
+struct TransientThingFactory;
+impl Factory for TransientThingFactory {
+    type Object = TransientThing;
+    fn build() -> Result<TransientThing, failure::Error> {
+        Ok(TransientThing(TRANSIENT_BUILDS.fetch_add(1, Ordering::SeqCst)))
+    }
+}
+
+aerosol::define_context!(
+    pub AppContext {
+        lazy_thing: LazyThing [lazy LazyThingFactory],
+        transient_thing: TransientThing [transient TransientThingFactory],
+    }
+);
+
+#[test]
+fn lazy_slot_builds_once_and_caches() {
+    let ctx = Arc::new(AppContext::new().unwrap());
+    let first = Provide::<LazyThing>::provide(&*ctx).0;
+    let second = Provide::<LazyThing>::provide(&*ctx).0;
+    assert_eq!(first, second);
+}
+
+#[test]
+fn transient_slot_builds_fresh_every_call() {
+    let before = TRANSIENT_BUILDS.load(Ordering::SeqCst);
+    let ctx = AppContext::new().unwrap();
+    let first = Provide::<TransientThing>::provide(&ctx).0;
+    let second = Provide::<TransientThing>::provide(&ctx).0;
+    assert_ne!(first, second);
+    assert!(TRANSIENT_BUILDS.load(Ordering::SeqCst) >= before + 2);
+}
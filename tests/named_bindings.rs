@@ -0,0 +1,73 @@
+//! Exercises named context slots (`field @ "name": Ty [Factory]`),
+//! `ProvideNamed`, `define_interface!`'s `as "name"` methods, and
+//! `DiContainer`'s named registration - checking that a
+//! macro-generated context and a runtime `DiContainer` both satisfy
+//! the same named interface.
+
+use aerosol::{Factory, DiContainer, ProvideNamed, failure};
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+struct AuditLogger(String);
+#[derive(Clone, Debug)]
+struct AppLogger(String);
+
+struct AuditLoggerFactory;
+impl Factory for AuditLoggerFactory {
+    type Object = Arc<AuditLogger>;
+    fn build() -> Result<Arc<AuditLogger>, failure::Error> {
+        Ok(Arc::new(AuditLogger("audit".into())))
+    }
+}
+
+struct AppLoggerFactory;
+impl Factory for AppLoggerFactory {
+    type Object = Arc<AppLogger>;
+    fn build() -> Result<Arc<AppLogger>, failure::Error> {
+        Ok(Arc::new(AppLogger("app".into())))
+    }
+}
+
+aerosol::define_interface!(
+    AuditInterface {
+        fn audit_logger(&self) -> Arc<AuditLogger> as "audit";
+    }
+);
+
+aerosol::define_interface!(
+    AppInterface: AuditInterface {
+        fn app_logger(&self) -> Arc<AppLogger> as "app";
+    }
+);
+
+aerosol::define_context!(
+    pub AppContext {
+        audit @ "audit": Arc<AuditLogger> [AuditLoggerFactory],
+        app @ "app": Arc<AppLogger> [AppLoggerFactory],
+    }
+);
+
+fn logger_names<I: AppInterface>(iface: &I) -> (String, String) {
+    (iface.audit_logger().0.clone(), iface.app_logger().0.clone())
+}
+
+#[test]
+fn macro_generated_context_satisfies_named_interface() {
+    let ctx = AppContext::new().unwrap();
+    assert_eq!(logger_names(&ctx), ("audit".to_string(), "app".to_string()));
+}
+
+#[test]
+fn runtime_container_satisfies_the_same_named_interface() {
+    let mut container = DiContainer::new();
+    container.register_named("audit", Arc::new(AuditLogger("audit2".into())));
+    container.register_named("app", Arc::new(AppLogger("app2".into())));
+    assert_eq!(logger_names(&container), ("audit2".to_string(), "app2".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "no dependency")]
+fn context_panics_resolving_an_unknown_name() {
+    let ctx = AppContext::new().unwrap();
+    ProvideNamed::<Arc<AuditLogger>>::provide_named(&ctx, "nonexistent");
+}
@@ -0,0 +1,49 @@
+//! Exercises resolution tracing. With the `trace` feature off (the
+//! default), `trace::log_build`/`wrap_build_error` are no-ops, so this
+//! just checks that building a context still works and that build
+//! errors still surface as errors. Run with `--features trace` to also
+//! exercise the `log`-backed path.
+
+use aerosol::{Factory, Provide, failure};
+
+#[derive(Clone, Debug)]
+struct Thing(u32);
+
+struct OkFactory;
+impl Factory for OkFactory {
+    type Object = Thing;
+    fn build() -> Result<Thing, failure::Error> {
+        Ok(Thing(1))
+    }
+}
+
+struct FailingFactory;
+impl Factory for FailingFactory {
+    type Object = Thing;
+    fn build() -> Result<Thing, failure::Error> {
+        Err(failure::format_err!("boom"))
+    }
+}
+
+aerosol::define_context!(
+    pub AppContext {
+        thing: Thing [OkFactory],
+    }
+);
+
+aerosol::define_context!(
+    pub FailingContext {
+        thing: Thing [FailingFactory],
+    }
+);
+
+#[test]
+fn tracing_does_not_change_a_successful_build() {
+    let ctx = AppContext::new().unwrap();
+    assert_eq!(Provide::<Thing>::provide(&ctx).0, 1);
+}
+
+#[test]
+fn tracing_does_not_swallow_a_build_error() {
+    assert!(FailingContext::new().is_err());
+}